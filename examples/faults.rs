@@ -0,0 +1,114 @@
+/*
+* Initialize the sensor under each class of injected faults, and check the driver returns an
+* error (instead of panicking, or going astray).
+*
+* Delays are not expected to fail init (nothing in the ULD measures wall time); for them, only
+* not panicking is checked.
+*/
+#![no_std]
+#![no_main]
+
+#[allow(unused_imports)]
+use defmt::{info, debug, error, warn, panic, assert};
+
+#[cfg(feature = "run_with_espflash")]
+use esp_println as _;
+#[cfg(feature = "run_with_probe_rs")]
+use defmt_rtt as _;
+
+use esp_backtrace as _;
+
+use esp_hal::{
+    delay::Delay,
+    gpio::{AnyPin, Output, OutputConfig, Level},
+    i2c::master::{Config as I2cConfig, I2c},
+    main,
+    time::Rate,
+    Blocking
+};
+
+extern crate just_b as uld;
+use uld::{
+    FaultInjectingPlatform,
+    Faults,
+    InitError,
+    PingPolicy,
+    VL53L5CX,
+};
+
+include!("./pins_gen.in");  // pins!
+
+mod pl;
+use pl::MyPlatform;
+
+#[allow(non_snake_case)]
+struct Pins {
+    SDA: AnyPin,
+    SCL: AnyPin,
+    PWR_EN: AnyPin,
+}
+
+#[allow(non_upper_case_globals)]
+const I2C_SPEED: Rate = Rate::from_khz(400);        // use max 400
+
+const SEED: u32 = 0x5eed;
+
+// (name, faults, must fail)
+const CLASSES: [(&str, Faults, bool); 3] = [
+    ("corrupt_read", Faults{ corrupt_read: 1000, drop_write: 0, delay: 0, delay_ms: 0 }, true),
+    ("drop_write", Faults{ corrupt_read: 0, drop_write: 1000, delay: 0, delay_ms: 0 }, true),
+    ("delay", Faults{ corrupt_read: 0, drop_write: 0, delay: 100, delay_ms: 20 }, false),
+];
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    #[allow(non_snake_case)]
+    let Pins{ SDA, SCL, PWR_EN } = pins!(peripherals);
+
+    #[allow(non_snake_case)]
+    let mut PWR_EN = Output::new(PWR_EN, Level::Low, OutputConfig::default());
+
+    let i2c: I2c<'static,Blocking> = I2c::new(peripherals.I2C0, I2cConfig::default()
+        .with_frequency(I2C_SPEED)
+    ).unwrap()
+        .with_sda(SDA)
+        .with_scl(SCL);
+
+    let mut pl = MyPlatform::new(i2c).warn_slow(200);
+
+    for (name, faults, must_fail) in CLASSES {
+        // Fresh sensor for each round
+        PWR_EN.set_low();
+        blocking_delay_ms(10);      // 10ms based on UM2884 (PDF; 18pp) Rev. 6, Chapter 4.2
+        PWR_EN.set_high();
+
+        // Skip the ping; we're after 'init()'. (The faults would have it fail already at the ping.)
+        //
+        let vl = VL53L5CX::builder(FaultInjectingPlatform::new(pl, faults, SEED))
+            .ping_policy(PingPolicy::Skip)
+            .build().unwrap();
+
+        let fpl = match vl.init() {
+            Ok(st) => {
+                let (fpl, _) = st.release();
+                assert!(!must_fail, "{}: init passed, with {} faults injected", name, fpl.injected());
+                info!("{}: init passed ({} faults injected)", name, fpl.injected());
+                fpl
+            },
+            Err(InitError{ error, p: fpl, .. }) => {
+                info!("{}: init failed, as it should: {} ({} faults injected)", name, error, fpl.injected());
+                fpl
+            }
+        };
+        pl = fpl.into_inner();
+    }
+
+    info!("All fault classes handled without a panic");
+    loop { blocking_delay_ms(1000); }
+}
+
+const D_PROVIDER: Delay = Delay::new();
+
+fn blocking_delay_ms(ms: u32) { D_PROVIDER.delay_millis(ms); }
@@ -23,7 +23,6 @@ use esp_hal::{
     Blocking
 };
 
-use core::cell::RefCell;
 
 extern crate just_b as uld;
 use uld::VL53L5CX;
@@ -65,15 +64,12 @@ fn main() -> ! {
             .with_scl(SCL)
     };
 
-    // Keep ownership of 'i2c' in 'main', while allowing it to be borrowed. If we moved it to 'MyPlatform',
-    // there would be problems in the handling of it (the way the lib uses 'MaybeUninit' vs. 'esp-hal'
-    // asserts); this also _feels_ like a better abstraction.
+    // The platform owns the bus; the library owns the platform (get it back by '.release()').
+    // No 'RefCell' (runtime borrow checks, and a possible panic) is needed.
     //
-    // Notes:
-    //  - 'RefCell'. Even this is just a "struct + counter" wrap and moves the ownership. Not good for us.
-    //
-    let i2c = RefCell::new(i2c);
-    let pl = MyPlatform::new(i2c);
+    // Firmware upload writes reach us in 4kB pieces; at 400kHz that's ~90ms each. Anything clearly
+    // above that is worth a warning.
+    let pl = MyPlatform::new(i2c).warn_slow(200);
 
     // Reset VL53L5CX(s) by pulling down their power for a moment
     {
@@ -83,7 +79,8 @@ fn main() -> ! {
         info!("Target powered off and on again.");
     }
 
-    let /*mut*/ x = VL53L5CX::new_with_ping(pl).unwrap();
+    let /*mut*/ x = VL53L5CX::builder(pl)
+        .build().unwrap();
 
     let mut vl = x.init()
         .expect("initialize to succeed");
@@ -4,23 +4,26 @@ use defmt::{assert, info, debug, error, warn, trace, panic};
 use esp_hal::{
     delay::Delay,
     i2c::master::{I2c, I2cAddress, Operation},
+    time::Instant,
     Blocking,
 };
 
-use core::cell::RefCell;
-
 use crate::uld::{
     DEFAULT_I2C_ADDR,
     I2cAddr,
     Custom,
 };
 
-const I2C_ADDR: I2cAddress = I2cAddress::SevenBit( DEFAULT_I2C_ADDR.as_7bit() );    // esp-hal address type
+const fn esp_addr(addr: &I2cAddr) -> I2cAddress {     // esp-hal address type
+    I2cAddress::SevenBit( addr.as_7bit() )
+}
 
 /*
 */
 pub struct MyPlatform {
-    i2c: RefCell<I2c<'static, Blocking>>
+    i2c: I2c<'static, Blocking>,
+    addr: I2cAddress,           // where the sensor currently responds
+    slow_ms: Option<u32>,       // warn about transactions taking longer than this
 }
 
 // Rust note: for the lifetime explanation, see:
@@ -29,22 +32,29 @@ pub struct MyPlatform {
 //
 impl MyPlatform {
     #[allow(non_snake_case)]
-    pub fn new(i2c: RefCell<I2c<'static, Blocking>>) -> Self {
-        Self{ i2c }
+    pub fn new(i2c: I2c<'static, Blocking>) -> Self {
+        Self{ i2c, addr: esp_addr(&DEFAULT_I2C_ADDR), slow_ms: None }
     }
 
-    fn with_i2c<R>(&mut self, f: impl FnOnce(&mut I2c<Blocking>) -> R) -> R {
-        let r = &mut self.i2c.borrow_mut();
-        f(r)
+    /* Warn about any single transaction taking longer than 'ms'. Helps find mis-clocked buses, or
+    * HALs doing per-byte interrupts (the firmware upload is ~84kB).
+    */
+    pub fn warn_slow(mut self, ms: u32) -> Self {
+        self.slow_ms = Some(ms);
+        self
     }
-}
 
-/* Do nothing on drop. This is needed because the 'lib' code, moving things to C structure, would
-* otherwise trigger a premature drop on 'I2c'.
-*/
-impl Drop for MyPlatform {
-    fn drop(&mut self) {
-        panic!("Platform dropped; don't like it");
+    fn check_slow(&self, t0: Instant, what: &str, index: u16, n: usize) {
+        if let Some(max) = self.slow_ms {
+            let ms = t0.elapsed().as_millis();
+            if ms > max as u64 {
+                warn!("Slow I2C {=str} at {:#06x} ({=usize} bytes): {=u64}ms (> {=u32}ms)", what, index, n, ms, max);
+            }
+        }
+    }
+
+    fn with_i2c<R>(&mut self, f: impl FnOnce(&mut I2c<Blocking>) -> R) -> R {
+        f(&mut self.i2c)
     }
 }
 
@@ -52,19 +62,23 @@ impl Custom for MyPlatform {
     /*
     */
     fn rd_bytes(&mut self, index: u16, buf: &mut [u8]) {
+        let t0 = Instant::now();
 
+        let addr = self.addr;
         self.with_i2c(|i2c| {
-            i2c.write_read(I2C_ADDR, &index.to_be_bytes(), buf)
+            i2c.write_read(addr, &index.to_be_bytes(), buf)
                 .unwrap_or_else(|e| {
                     // If we get an error, let's stop right away.
                     panic!("I2C read at {:#06x} ({=usize} bytes) failed: {}", index, buf.len(), e);
                 });
         });
+        self.check_slow(t0, "read", index, buf.len());
 
-        if buf.len() <= 20 {
+        #[cfg(feature = "trace_io")]
+        if buf.len() <= TRACE_SLICE_HEAD {
             trace!("I2C read: {:#06x} -> {:#04x}", index, buf);
         } else {
-            trace!("I2C read: {:#06x} -> {:#04x}... ({} bytes)", index, slice_head(buf,20), buf.len());
+            trace!("I2C read: {:#06x} -> {:#04x}... ({} bytes)", index, slice_head(buf,TRACE_SLICE_HEAD), buf.len());
         }
 
         // There should be 1.3ms between transmissions, by the VL spec. (see 'tBUF', p.15)
@@ -72,33 +86,39 @@ impl Custom for MyPlatform {
     }
 
     /***
-    * Vendor ULD driver calls us with chunk lengths of 32768, during the initialization.
+    * Vendor ULD driver writes chunks of 32768 bytes during the initialization; the library passes
+    * them on to us in 4096 byte pieces.
     *
     * IF we get errors from the HAL, we panic. ULD C level would often go on for too long; it's best
     * to stop early. CERTAIN error codes MAY lead to a single retry, if we think we have a chance
     * to recover.
     */
     fn wr_bytes(&mut self, index: u16, vs: &[u8]) {
-        const TRACE_SLICE_HEAD: usize = 20;
-
-        trace!("Writing: {:#06x} <- {:#04x}", index, slice_head(vs,20));    // TEMP
+        #[cfg(feature = "trace_io")]
+        trace!("Writing: {:#06x} <- {:#04x}", index, slice_head(vs,TRACE_SLICE_HEAD));    // TEMP
 
         // 'esp-hal' doesn't have '.write_write()', but it's easy to make one. This means we don't
         // need to concatenate the slices in a buffer.
         //
         // BUG: GETS STUCK (FIRST WRITE AFTER INIT) HERE:
+        let t0 = Instant::now();
+        let addr = self.addr;
         self.with_i2c(|i2c| {
-            i2c.transaction(I2C_ADDR, &mut [Operation::Write(&index.to_be_bytes()), Operation::Write(&vs)])
+            i2c.transaction(addr, &mut [Operation::Write(&index.to_be_bytes()), Operation::Write(&vs)])
                 .unwrap_or_else(|e| {
                     panic!("I2C write to {:#06x} ({} bytes) failed: {}", index, vs.len(), e);
                 });
         });
-
-        let n = vs.len();
-        if n <= TRACE_SLICE_HEAD {
-            trace!("I2C written: {:#06x} <- {:#04x}", index, vs);
-        } else {
-            trace!("I2C written: {:#06x} <- {:#04x}... ({=usize} bytes)", index, slice_head(vs,TRACE_SLICE_HEAD), n);
+        self.check_slow(t0, "write", index, vs.len());
+
+        #[cfg(feature = "trace_io")]
+        {
+            let n = vs.len();
+            if n <= TRACE_SLICE_HEAD {
+                trace!("I2C written: {:#06x} <- {:#04x}", index, vs);
+            } else {
+                trace!("I2C written: {:#06x} <- {:#04x}... ({=usize} bytes)", index, slice_head(vs,TRACE_SLICE_HEAD), n);
+            }
         }
 
         // There should be 1.3ms between transmissions, by the VL spec. (see 'tBUF', p.15)
@@ -110,11 +130,22 @@ impl Custom for MyPlatform {
         blocking_delay_us(ms*1000);
     }
 
-    fn addr_changed(&mut self, _: &I2cAddr) {
-        unimplemented!()
+    /* Either the ULD changed the address, or the application told us (via the builder) the
+    * sensor is already at another address (e.g. kept powered over an MCU reset).
+    */
+    fn addr_changed(&mut self, addr: &I2cAddr) {
+        debug!("I2C address now {}", addr);
+        self.addr = esp_addr(addr);
     }
 }
 
+// Per-transaction tracing is only compiled in with the 'trace_io' feature. Even when 'DEFMT_LOG'
+// filters 'trace' out, the feature keeps the (bulky) I/O traces out of sight, unless asked for.
+//
+#[cfg(feature = "trace_io")]
+const TRACE_SLICE_HEAD: usize = 20;
+
+#[cfg(feature = "trace_io")]
 fn slice_head(vs: &[u8],n_max: usize) -> &[u8] {
     use core::cmp::min;
     &vs[..min(vs.len(),n_max)]
@@ -0,0 +1,64 @@
+/*
+* Builder for 'VL53L5CX', collecting the boot options before the first contact with the sensor.
+*/
+use defmt::debug;
+
+use crate::{BuildError, CoreResult, Custom, I2cAddr, PingPolicy, VL53L5CX};
+
+pub struct Builder<'a, P: Custom + 'static> {
+    p: P,
+    addr: Option<I2cAddr>,
+    ping_policy: PingPolicy<'a>,
+    label: &'static str,
+}
+
+impl<P: Custom + 'static> Builder<'static, P> {
+    pub(crate) fn new(p: P) -> Self {
+        Self{ p, addr: None, ping_policy: PingPolicy::Strict, label: "VL53L5CX" }
+    }
+}
+
+impl<'a, P: Custom + 'static> Builder<'a, P> {
+    /*
+    * I2C address the sensor currently responds to (default: 'DEFAULT_I2C_ADDR'). The platform is
+    * informed (via '.addr_changed()') before the ping.
+    *
+    * Use this for a sensor that retained a changed address (e.g. stayed powered over an MCU reset);
+    * it can be initialized there, without a power cycle ('vl53l5cx_init()' starts by rebooting
+    * the sensor, in software).
+    */
+    pub fn address(mut self, addr: I2cAddr) -> Self {
+        self.addr = Some(addr);
+        self
+    }
+
+    pub fn ping_policy<'b>(self, policy: PingPolicy<'b>) -> Builder<'b, P> {
+        let Self{ p, addr, label, .. } = self;
+        Builder{ p, addr, ping_policy: policy, label }
+    }
+
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /*
+    * Pings the sensor (as per the ping policy). Nothing else is written to it.
+    *
+    * If the ping isn't accepted, returns 'BuildError' with 'Error::WrongDevice{..}' (carrying the
+    * id's read), and the platform given back.
+    */
+    pub fn build(self) -> CoreResult<VL53L5CX<P>, BuildError<P>> {
+        let Self{ mut p, addr, ping_policy, label } = self;
+
+        if let Some(addr) = addr {
+            debug!("{=str}: using I2C address {}", label, addr);
+            p.addr_changed(&addr);
+        }
+
+        match VL53L5CX::ping(&mut p, ping_policy) {
+            Ok(id) => Ok(VL53L5CX{ p, label, id }),
+            Err(error) => Err(BuildError{ error, p })
+        }
+    }
+}
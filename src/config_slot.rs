@@ -0,0 +1,53 @@
+/*
+* ConfigSlot
+*
+* Storage for the ULD "configuration" (its working memory; ~2.3KB). By default, it's kept within
+* the state struct, but the application may also provide a slot of its own (e.g. a 'static'),
+* so that the struct never transits the stack.
+*/
+use core::mem::MaybeUninit;
+
+use crate::{Custom, Result};
+use crate::uld_raw::VL53L5CX_Configuration;
+
+pub struct ConfigSlot(MaybeUninit<VL53L5CX_Configuration>);
+
+impl ConfigSlot {
+    pub const fn new() -> Self {
+        Self(MaybeUninit::uninit())
+    }
+
+    pub(crate) fn init(&mut self, p: &mut dyn Custom) -> Result<()> {
+        unsafe { VL53L5CX_Configuration::init_at(self.0.as_mut_ptr(), p) }
+    }
+
+    // Note: Only to be called after a successful '.init()'. The states guarantee this.
+    pub(crate) fn uld(&mut self) -> &mut VL53L5CX_Configuration {
+        unsafe { self.0.assume_init_mut() }
+    }
+}
+
+impl Default for ConfigSlot {
+    fn default() -> Self { Self::new() }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/*
+* Where a state keeps its 'ConfigSlot': either inline, or borrowed for good.
+*/
+pub trait UldStorage: sealed::Sealed {
+    fn slot(&mut self) -> &mut ConfigSlot;
+}
+
+impl sealed::Sealed for ConfigSlot {}
+impl UldStorage for ConfigSlot {
+    fn slot(&mut self) -> &mut ConfigSlot { self }
+}
+
+impl sealed::Sealed for &'static mut ConfigSlot {}
+impl UldStorage for &'static mut ConfigSlot {
+    fn slot(&mut self) -> &mut ConfigSlot { self }
+}
@@ -0,0 +1,108 @@
+/*
+* FaultInjectingPlatform
+*
+* Decorator for any 'Custom' platform, injecting faults into the transactions, by a seeded
+* (thus reproducible) schedule. Meant for robustness testing: the driver should return errors,
+* not panic or go astray, under each fault class.
+*
+* Note: 'Custom' methods don't return errors, so the faults are of the "silent" kind: corrupted
+*       read data, writes that never reach the bus, and late transactions. It's up to the ULD C
+*       code to notice them (e.g. timeouts while polling for an answer).
+*/
+use defmt::warn;
+
+use crate::{Custom, I2cAddr};
+
+/*
+* Probabilities of each fault class, in per mille (0..=1000) of transactions.
+*
+* Construct with '..Default::default()' for the classes you don't need; this keeps working if
+* more classes are added.
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Default, core::fmt::Debug)]
+pub struct Faults {
+    pub corrupt_read: u16,      // flip the bits of one byte of read data
+    pub drop_write: u16,        // don't pass the write on
+    pub delay: u16,             // wait 'delay_ms' before passing the transaction on
+    pub delay_ms: u32,
+}
+
+pub struct FaultInjectingPlatform<P: Custom> {
+    p: P,
+    faults: Faults,
+    rng: u32,                   // xorshift32 state; never 0
+    injected: u32,              // number of faults injected so far
+}
+
+impl<P: Custom> FaultInjectingPlatform<P> {
+    pub fn new(p: P, faults: Faults, seed: u32) -> Self {
+        Self{ p, faults, rng: if seed == 0 { 1 } else { seed }, injected: 0 }
+    }
+
+    pub fn injected(&self) -> u32 { self.injected }
+
+    pub fn into_inner(self) -> P { self.p }
+
+    // xorshift32 (Marsaglia); good enough for a test schedule.
+    fn next(&mut self) -> u32 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+        x
+    }
+
+    fn roll(&mut self, per_mille: u16) -> bool {
+        let hit = (self.next() % 1000) < per_mille as u32;
+        if hit { self.injected += 1; }
+        hit
+    }
+
+    fn maybe_delay(&mut self, index: u16) {
+        if self.roll(self.faults.delay) {
+            warn!("Fault: delaying transaction at {:#06x} by {=u32}ms", index, self.faults.delay_ms);
+            self.p.delay_ms(self.faults.delay_ms);
+        }
+    }
+}
+
+impl<P: Custom> Custom for FaultInjectingPlatform<P> {
+    fn rd_bytes(&mut self, index: u16, buf: &mut [u8]) {
+        self.maybe_delay(index);
+        self.p.rd_bytes(index, buf);
+
+        if !buf.is_empty() && self.roll(self.faults.corrupt_read) {
+            let i = self.next() as usize % buf.len();
+            warn!("Fault: corrupting read at {:#06x} (byte {=usize} of {=usize})", index, i, buf.len());
+            buf[i] ^= 0xff;
+        }
+    }
+
+    fn wr_bytes(&mut self, index: u16, vs: &[u8]) {
+        self.maybe_delay(index);
+
+        if self.roll(self.faults.drop_write) {
+            warn!("Fault: dropping write to {:#06x} ({=usize} bytes)", index, vs.len());
+            return;
+        }
+        self.p.wr_bytes(index, vs)
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.p.delay_ms(ms)
+    }
+
+    fn addr_changed(&mut self, addr: &I2cAddr) {
+        self.p.addr_changed(addr)
+    }
+
+    fn max_read_len(&self) -> usize {
+        self.p.max_read_len()
+    }
+
+    fn on_progress(&mut self) {
+        self.p.on_progress()
+    }
+}
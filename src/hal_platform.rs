@@ -0,0 +1,90 @@
+/*
+* HalPlatform
+*
+* Ready-made 'Custom' platform over 'embedded-hal' 1.0 'I2c' and 'DelayNs', so that MCUs with an
+* 'embedded-hal' implementation don't need to write the index-prefixed reads and writes by hand.
+*
+* Note: 'Custom' methods don't return errors. I2C errors are logged and counted ('.errors()'); a
+*       failed read gives zeros. It's up to the ULD C code to notice (it will time out, or reject
+*       the data), and the application to check '.errors()' for the cause.
+*/
+use defmt::error;
+
+use embedded_hal::{
+    delay::DelayNs,
+    i2c::{Error as _, I2c, Operation},
+};
+
+use crate::{Custom, I2cAddr, DEFAULT_I2C_ADDR};
+
+pub struct HalPlatform<I: I2c, D: DelayNs, F: FnMut() = fn()> {
+    i2c: I,
+    delay: D,
+    addr: u8,           // 7-bit; where the sensor currently responds
+    errors: u32,        // failed I2C transactions so far
+    progress: Option<F>,        // see '.with_progress()'
+}
+
+impl<I: I2c, D: DelayNs> HalPlatform<I, D> {
+    pub fn new(i2c: I, delay: D) -> Self {
+        Self{ i2c, delay, addr: DEFAULT_I2C_ADDR.as_7bit(), errors: 0, progress: None }
+    }
+}
+
+impl<I: I2c, D: DelayNs, F: FnMut()> HalPlatform<I, D, F> {
+    /* Closure to call on 'Custom::on_progress()'; e.g. to feed a hardware watchdog, moved in:
+    *
+    *   <<
+    *       let pl = HalPlatform::new(i2c, delay)
+    *           .with_progress(move || wdt.feed());
+    *   <<
+    */
+    pub fn with_progress<G: FnMut()>(self, f: G) -> HalPlatform<I, D, G> {
+        let Self{ i2c, delay, addr, errors, .. } = self;
+        HalPlatform{ i2c, delay, addr, errors, progress: Some(f) }
+    }
+
+    pub fn errors(&self) -> u32 { self.errors }
+
+    pub fn into_inner(self) -> (I, D) { (self.i2c, self.delay) }
+
+    fn failed(&mut self, e: I::Error, what: &str, index: u16, n: usize) {
+        self.errors = self.errors.saturating_add(1);
+        error!("I2C {=str} at {:#06x} ({=usize} bytes) failed: {}", what, index, n, defmt::Debug2Format(&e.kind()));
+    }
+
+    // There should be 1.3ms between transmissions, by the VL spec. (see 'tBUF', p.15)
+    fn t_buf(&mut self) {
+        self.delay.delay_us(1300);
+    }
+}
+
+impl<I: I2c, D: DelayNs, F: FnMut()> Custom for HalPlatform<I, D, F> {
+    fn rd_bytes(&mut self, index: u16, buf: &mut [u8]) {
+        if let Err(e) = self.i2c.write_read(self.addr, &index.to_be_bytes(), buf) {
+            buf.fill(0);
+            self.failed(e, "read", index, buf.len());
+        }
+        self.t_buf();
+    }
+
+    // Adjacent write operations are sent without a restart; no need to concatenate the slices.
+    fn wr_bytes(&mut self, index: u16, vs: &[u8]) {
+        if let Err(e) = self.i2c.transaction(self.addr, &mut [Operation::Write(&index.to_be_bytes()), Operation::Write(vs)]) {
+            self.failed(e, "write", index, vs.len());
+        }
+        self.t_buf();
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay.delay_ms(ms)
+    }
+
+    fn addr_changed(&mut self, addr: &I2cAddr) {
+        self.addr = addr.as_7bit();
+    }
+
+    fn on_progress(&mut self) {
+        if let Some(f) = &mut self.progress { f() }
+    }
+}
@@ -1,24 +1,86 @@
 #![no_std]
 #![allow(non_snake_case)]
 
+// Feature combinations that would otherwise surface as confusing errors (linker, or unresolved
+// imports) further down the line.
+//
+#[cfg(all(feature = "run_with_espflash", feature = "run_with_probe_rs"))]
+compile_error!("Features 'run_with_espflash' and 'run_with_probe_rs' are mutually exclusive (both provide a 'defmt' logger).");
+
+#[cfg(not(feature = "_defmt"))]
+compile_error!("The library currently requires 'defmt'; enable the '_defmt' feature ('run_with_*' are only for the examples).");
+
+#[cfg(all(feature = "assert_defmt", feature = "assert_callback"))]
+compile_error!("Features 'assert_defmt' and 'assert_callback' are mutually exclusive.");
+
+/*
+* Internal (runtime) checks. The backend is selected by features:
+*   - 'assert_defmt': 'defmt::assert!'
+*   - 'assert_callback': 'uld_assert_failed()', provided by the application (see below)
+*   - neither: 'core::assert!'
+*
+* Note: Keep the messages to plain '{}' placeholders; they need to work with both 'defmt' and
+*       'core::fmt' formatting.
+*/
+macro_rules! uld_assert {
+    ($cond:expr, $($arg:tt)+) => {
+        #[cfg(feature = "assert_defmt")]
+        defmt::assert!($cond, $($arg)+);
+        #[cfg(feature = "assert_callback")]
+        if !($cond) { unsafe { $crate::uld_assert_failed(format_args!($($arg)+)) } }
+        #[cfg(not(any(feature = "assert_defmt", feature = "assert_callback")))]
+        core::assert!($cond, $($arg)+);
+    };
+}
+
+#[cfg(feature = "assert_callback")]
+unsafe extern "Rust" {
+    // Provided by the application, as:
+    //  <<
+    //      #[unsafe(no_mangle)]
+    //      fn uld_assert_failed(args: core::fmt::Arguments) -> ! { ... }
+    //  <<
+    pub(crate) fn uld_assert_failed(args: core::fmt::Arguments) -> !;
+}
+
+mod builder;
+mod config_slot;
+#[cfg(feature = "fault_injection")]
+mod fault_injecting;
+#[cfg(feature = "embedded_hal")]
+mod hal_platform;
 mod platform;
+mod sensor_config;
 mod state_hp_idle;
+mod state_lp_idle;
 mod uld_raw;
 
-use defmt::{debug, error, Format};
+use defmt::{error, info, warn, Format};
 
 use core::{
     fmt::{Display, Formatter},
     result::Result as CoreResult,
 };
 
+#[cfg(feature = "embedded_hal")]
+pub use hal_platform::HalPlatform;
+
+#[cfg(feature = "fault_injection")]
+pub use fault_injecting::{FaultInjectingPlatform, Faults};
+
 pub use {
+    builder::Builder,
+    config_slot::{ConfigSlot, UldStorage},
     platform::Custom,
-    state_hp_idle::State_HP_Idle,
+    sensor_config::{PowerMode, Profile, SensorConfig, SensorStateKind, TargetOrder},
+    state_hp_idle::{FrameBudget, State_HP_Idle, Stats},
+    state_lp_idle::State_LP_Idle,
 };
 
+use crate::state_hp_idle::TARGETS;
 use crate::uld_raw::{
     VL53L5CX_Configuration,
+    VL53L5CX_Platform,
     vl53l5cx_init,
     ST_OK, ST_ERROR,
 };
@@ -27,14 +89,120 @@ pub type Result<T> = core::result::Result<T,Error>;
 
 #[cfg_attr(feature = "_defmt", derive(defmt::Format))]
 #[derive(core::fmt::Debug)]
-pub struct Error(pub u8);
+#[non_exhaustive]
+pub enum Error {
+    Uld(u8),            // status code from the ULD C API (or the platform, via the tunnel); never 255
+    UldGeneric(UldOp),  // ULD C API status 255 ("major error"; usually I2C timeout), with what we were doing
+    PlatformLayout,     // C side 'VL53L5CX_Platform' cannot host the Rust 'Custom'; edit 'platform.h'
+    OverBudget(FrameBudget),    // configuration cannot physically meet the requested frequency
+    Mismatch{ bindings: u8, library: u8 },  // targets per zone: what the Rust bindings were made for vs. what the ULD C library programmed
+    WrongDevice{ dev: u8, rev: u8 },    // ping read these '(device id, rev id)'; '0xff's (or '0x00's) usually mean nothing answered
+    LinkLost(u8),       // sensor not responding sensibly (number of consecutive failed checks)
+    SensorRebooted,     // sensor answers its ID, but keeps failing checks; likely rebooted (lost its firmware)
+}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "ULD driver or hardware error ({})", self.0)
+        match self {
+            Error::Uld(st) => write!(f, "ULD driver or hardware error ({})", st),
+            Error::UldGeneric(op) => write!(f, "ULD driver or hardware error (255) during {:?}", op),
+            Error::PlatformLayout => write!(f, "platform tunnel size or alignment mismatch between C and Rust"),
+            Error::OverBudget(b) => write!(f, "integration time ({}ms) does not fit the frame period ({}ms)", b.integration_ms, b.period_ms),
+            Error::Mismatch{ bindings, library } => write!(f, "targets per zone mismatch (bindings {}, ULD library {})", bindings, library),
+            Error::WrongDevice{ dev, rev } => write!(f, "unexpected device at the address (device id {:#04x}, rev id {:#04x})", dev, rev),
+            Error::LinkLost(n) => write!(f, "link to sensor lost ({} failed checks in a row)", n),
+            Error::SensorRebooted => write!(f, "sensor answers, but keeps failing checks (likely rebooted)")
+        }
+    }
+}
+
+/*
+* Failed '.build()'. Gives back the platform, so that the bus can be used for another attempt (e.g.
+* at another '.address()'), or for the next sensor.
+*/
+pub struct BuildError<P> {
+    pub error: Error,
+    pub p: P,
+}
+
+impl<P> core::fmt::Debug for BuildError<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "BuildError({:?})", self.error)
+    }
+}
+
+#[cfg(feature = "_defmt")]
+impl<P> Format for BuildError<P> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "BuildError({})", self.error)
+    }
+}
+
+impl<P> Display for BuildError<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "build failed: {}", self.error)
+    }
+}
+
+/*
+* Failed '.init()' or '.init_in()'. Gives back what was moved in, so that the sensor can be
+* retried. 'slot' is '()' for '.init()', where the working memory was inline.
+*/
+pub struct InitError<P, S = ()> {
+    pub error: Error,
+    pub p: P,
+    pub slot: S,
+}
+
+impl<P, S> core::fmt::Debug for InitError<P,S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "InitError({:?})", self.error)
+    }
+}
+
+#[cfg(feature = "_defmt")]
+impl<P, S> Format for InitError<P,S> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "InitError({})", self.error)
+    }
+}
+
+impl<P, S> Display for InitError<P,S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "init failed: {}", self.error)
     }
 }
 
+impl Error {
+    /* Error from a non-OK ULD status. 255 is the vendor's generic "major error"; keep it apart from
+    * the others, and tag it with the failed operation.
+    *
+    * Note: 255 is also "no target" in the per-zone results; that is not an API error, and never
+    *       goes through here.
+    */
+    pub(crate) fn from_uld(st: u8, op: UldOp) -> Self {
+        match st {
+            ST_ERROR => Error::UldGeneric(op),
+            st => Error::Uld(st)
+        }
+    }
+}
+
+/*
+* The operation that failed, for 'Error::UldGeneric'.
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Eq, PartialEq, core::fmt::Debug)]
+#[non_exhaustive]
+pub enum UldOp {
+    Init,
+    GetPowerMode,
+    SetPowerMode,
+    GetConfig,
+    SetConfig,
+    DciRead,
+}
+
 pub const DEFAULT_I2C_ADDR: I2cAddr = I2cAddr::from_8bit(0x52);    // default after each power on
 
 /*
@@ -45,12 +213,15 @@ pub const DEFAULT_I2C_ADDR: I2cAddr = I2cAddr::from_8bit(0x52);    // default af
 *       those fields non-pub in the 'bindgen' phase, and be able to pass this struct, directly. #design
 */
 impl VL53L5CX_Configuration {
-    /** @brief Returns a default 'VL53L5CX_Configuration' struct, spiced with the application
-       * provided 'Platform'-derived state (opaque to us, except for its size).
+    /** @brief Initializes a 'VL53L5CX_Configuration' struct in place (at 'up'), spiced with a
+       * pointer to the application provided 'Custom' platform.
+       *
+       * Note: The struct is ~2.3KB. Initializing it in place allows the caller to decide, where it
+       *       lives (see 'ConfigSlot').
        *
        * Initialized state is (as per ULD C code):
        *   <<
-       *       .platform: dyn Platform     = anything the app keeps there
+       *       .platform: *mut dyn Custom  = pointer to the app's platform (see '.attach()')
        *       .streamcount: u8            = 0 (undefined by ULD C code)
        *       .data_read_size: u32        = 0 (undefined by ULD C code)
        *       .default_configuration: *mut u8 = VL53L5CX_DEFAULT_CONFIGURATION (a const table)
@@ -75,65 +246,33 @@ impl VL53L5CX_Configuration {
        *   - two bytes updated at sensor's DCI memory at '0x0e108' ('VL53L5CX_GLARE_FILTER'):
        *       {0x01, 0x01}
     */
-    fn init_with(mut p: impl Custom) -> Result<Self> {
-        use core::{
-            mem::MaybeUninit,
-            ptr::addr_of_mut
-        };
-        #[allow(unused_imports)]
-        use core::ffi::c_void;
-
-        let ret: Result<VL53L5CX_Configuration> = unsafe {
-            let mut uninit = MaybeUninit::<VL53L5CX_Configuration>::uninit();
-                // note: use '::zeroed()' in place of '::uninit()' to get more predictability
-            let up = uninit.as_mut_ptr();
+    unsafe fn init_at(up: *mut Self, p: &mut dyn Custom) -> Result<()> {
+        use core::ptr::addr_of_mut;
 
+        unsafe {
             // Check that the size and alignments are as expected.
             {
                 let pp = addr_of_mut!((*up).platform);
 
                 // Check size and alignment
                 //
-                // Note: It's difficult (it seems) to make the C side both 8-aligned and 20-wide.
-                //      The compiler makes the struct 24-wide, in that case. So we allow the gap.
+                // The C side only carries a (fat) pointer to the 'Custom'; the platform itself is
+                // owned by the Rust side state. The size is checked at compile time; alignment is
+                // about the actual address, and is checked at runtime.
                 //
-                let sz_c = size_of_val(&(*up).platform);
-                let sz_rust = size_of_val(&p);
-                assert!(sz_c >= sz_rust, "Tunnel C side isn't wide enough");   // edit 'platform.h' to adjust
-
-                let al_rust = align_of_val(&p);
-                assert!( (pp as usize)%al_rust == 0 ||false, "bad alignment on C side (needs {})", al_rust );
-
-                debug!("C size: {}, Rust size and alignment: {} {}", sz_c, sz_rust, al_rust );  // 24 20 4
+                const {
+                    assert!(size_of::<VL53L5CX_Platform>() >= size_of::<*mut dyn Custom>(), "Tunnel C side isn't wide enough");   // edit 'platform.h' to adjust
+                }
+                let al_rust = align_of::<*mut dyn Custom>();
+                if (pp as usize)%al_rust != 0 {
+                    error!("Bad alignment on C side (needs {})", al_rust);
+                    return Err(Error::PlatformLayout);
+                }
             }
 
-            // Make a bitwise copy of 'Custom' in 'uninit.platform'; ULD C 'vl.._init()' will need it,
-            // to access the I2C bus (below).
-            //
-            // Note! Very important 'Custom' doesn't get dropped.
-            {
-                let pp = addr_of_mut!((*up).platform);
-
-                *(pp as *mut &mut dyn Custom) = (&mut p) as &mut dyn Custom;
-                core::mem::forget(p);
-
-                /*** shoo off
-                // "cannot transmute between types of different sizes, or dependently-sized types"
-                // *pp = unsafe { transmute(p) };
-
-                //(*pp).__ = unsafe { transmute(&mut p as *mut dyn Custom) };
-
-                // works, but allows a Drop
-                // *(pp as *mut &mut dyn Custom) = (&mut p) as &mut dyn Custom;
-
-                // this is just for getting fields from within struct
-                //let mut a = unsafe { core::ptr::read(&p) };     // moves data out, ensures it’s not dropped
-
-                //let dd: &dyn Custom = unsafe { transmute(p) };
-
-                *(_pp as *mut &mut dyn Custom) = (&mut p) as &mut dyn Custom;
-                ***/
-            }
+            // Point '.platform' to the 'Custom'; ULD C 'vl.._init()' will need it, to access
+            // the I2C bus (below).
+            Self::attach_at(up, p);
 
             // Initialize those fields we know C API won't touch (just in case)
             addr_of_mut!((*up).streamcount).write(u8::MAX);
@@ -144,53 +283,188 @@ impl VL53L5CX_Configuration {
             // Note: Already this will call the platform methods (via the tunnel).
             //
             match vl53l5cx_init(up) {
-                ST_OK => Ok(uninit.assume_init()),  // we guarantee it's now initialized
-                e => Err(Error(e))
+                ST_OK => Ok(()),    // '*up' is now initialized
+                e => Err(Error::from_uld(e, UldOp::Init))
             }
-        };
-        ret
+        }
+    }
+
+    /*
+    * Point the C side 'platform' slot to the application's 'Custom'.
+    *
+    * The platform is owned by the Rust side state ('State_HP_Idle' etc.), which may have moved since
+    * the last ULD call. Thus, this needs to be done before *each* call to the ULD C API; a stale
+    * pointer must never reach the tunnel.
+    */
+    pub(crate) fn attach(&mut self, p: &mut dyn Custom) {
+        unsafe { Self::attach_at(self, p) }
+    }
+
+    // Same as '.attach()', but also for a not-yet-initialized struct.
+    unsafe fn attach_at(up: *mut Self, p: &mut dyn Custom) {
+        let pp: *mut VL53L5CX_Platform = unsafe { core::ptr::addr_of_mut!((*up).platform) };
+
+        unsafe { *(pp as *mut *mut (dyn Custom + '_)) = p as *mut (dyn Custom + '_); }
     }
 }
 
+/*
+* Type erased sensor: the platform is behind a '&'static mut dyn Custom'. Sensors on different
+* kinds of platforms (e.g. two I2C buses, or I2C + SPI) then share a single copy of the driver
+* code, instead of one per platform type.
+*
+*   <<
+*       static PL: StaticCell<MyPlatform> = StaticCell::new();
+*       let vl: DynSensor = VL53L5CX::builder(PL.init(pl) as &mut dyn Custom).build()?;
+*   <<
+*/
+pub type DynSensor = VL53L5CX<&'static mut dyn Custom>;
+
 /*
 * Access to a single VL53L5CX sensor.
 */
 pub struct VL53L5CX<P: Custom + 'static> {
-    p: P
+    p: P,
+    label: &'static str,    // for logs; tells sensors apart
+    id: Option<(u8,u8)>,    // '(device id, rev id)' accepted at ping; 'None' if the ping was skipped
 }
 
 impl<P: Custom + 'static> VL53L5CX<P> {
     /*
-    * Instead of just creating this structure, this already pings the bus to see, whether there's
-    * a suitable sensor out there.
+    * Start bringing up a sensor. The boot options are given to the builder; '.build()' pings the
+    * bus to see, whether there's a suitable sensor out there.
+    *
+    *   <<
+    *       let vl = VL53L5CX::builder(pl)
+    *           .ping_policy(PingPolicy::Strict)    // default
+    *           .label("left")
+    *           .build()?;
+    *   <<
     */
-    pub fn new_with_ping(/*move*/ mut p: P) -> Result<Self> {
-        match Self::ping(&mut p) {
-            Err(_) => Err(Error(ST_ERROR)),
-            Ok(()) => Ok(Self{ p })
-        }
+    pub fn builder(/*move*/ p: P) -> Builder<'static, P> {
+        Builder::new(p)
+    }
+
+    /*
+    * Note: On failure, the platform is given back (in 'InitError'), for a retry.
+    *
+    * Note: The ULD working memory (~2.3KB) is created on the stack, and moved into the returned
+    *       state. If that's too much for your (task) stack, use '.init_in()'.
+    */
+    pub fn init(self) -> CoreResult<State_HP_Idle<P>, InitError<P>> {
+        self.init_into(ConfigSlot::new())
+            .map_err(|InitError{ error, p, .. }| InitError{ error, p, slot: () })
     }
 
-    pub fn init(self) -> Result<State_HP_Idle> {
-        let uld = VL53L5CX_Configuration::init_with(self.p)?;
+    /*
+    * Like '.init()', but the ULD working memory is in a caller provided (e.g. 'static') slot, and
+    * initialized in place. No multi-KB stack spikes.
+    *
+    * On failure, both the platform and the slot are given back (in 'InitError'); a '&'static mut'
+    * cannot be had a second time.
+    */
+    pub fn init_in(self, slot: &'static mut ConfigSlot) -> CoreResult<State_HP_Idle<P, &'static mut ConfigSlot>, InitError<P, &'static mut ConfigSlot>> {
+        self.init_into(slot)
+    }
 
-        Ok( State_HP_Idle::new(uld) )
+    fn init_into<S: UldStorage>(mut self, mut slot: S) -> CoreResult<State_HP_Idle<P,S>, InitError<P,S>> {
+        let mut tally = Tally::new(&mut self.p);
+        let r = slot.slot().init(&mut tally);
+
+        // One record summarizing what init did, over the bus; also (especially) if it failed.
+        match r {
+            Ok(()) => {
+                info!("{=str}: Init: {=usize} bytes written (firmware, config) in {=usize} ops; {=usize} bytes read (NVM, status) in {=usize} ops; {=u32}ms of waits; {=u8} target(s) per zone, glare filter on",
+                    self.label, tally.wr_bytes, tally.wr_ops, tally.rd_bytes, tally.rd_ops, tally.delay_ms, TARGETS
+                );
+                Ok( State_HP_Idle::new(slot, self.p, self.label, self.id) )
+            },
+            Err(error) => {
+                error!("{=str}: Init failed ({}), after: {=usize} bytes written in {=usize} ops; {=usize} bytes read in {=usize} ops; {=u32}ms of waits",
+                    self.label, error, tally.wr_bytes, tally.wr_ops, tally.rd_bytes, tally.rd_ops, tally.delay_ms
+                );
+                Err( InitError{ error, p: self.p, slot } )
+            }
+        }
     }
 
-    fn ping(p: &mut P) -> CoreResult<(),()> {
-        match vl53l5cx_ping(p)? {
-            (a@ 0xf0, b@ 0x02) => {     // vendor driver ONLY proceeds with this
-                debug!("Ping succeeded: {=u8:#04x},{=u8:#04x}", a,b);
-                Ok(())
+    /* Returns the accepted '(device id, rev id)'; 'None' if the ping was skipped.
+    */
+    pub(crate) fn ping(p: &mut P, policy: PingPolicy) -> Result<Option<(u8,u8)>> {
+        if let PingPolicy::Skip = policy {
+            warn!("Ping skipped; device identity not checked");
+            return Ok(None);
+        }
+
+        match vl53l5cx_ping(p) {
+            (0xf0, 0x02) => Ok(Some((0xf0, 0x02))),     // vendor driver ONLY proceeds with this
+            (a,b) if matches!(policy, PingPolicy::AcceptList(vs) if vs.contains(&(a,b))) => {
+                warn!("Accepting '(device id, rev id)' {=u8:#04x},{=u8:#04x}; vendor driver expects 0xf0,0x02", a,b);
+                Ok(Some((a,b)))
             },
-            t => {
-                error!("Unexpected '(device id, rev id)': {:#04x}", t);
-                Err(())
+            (dev, rev) => {
+                error!("Unexpected '(device id, rev id)': {=u8:#04x},{=u8:#04x}", dev, rev);
+                Err(Error::WrongDevice{ dev, rev })
             }
         }
     }
 }
 
+/*
+* Platform wrapper that counts the traffic passing through it. Used for summarizing 'init()'.
+*/
+struct Tally<'a> {
+    p: &'a mut dyn Custom,
+    rd_bytes: usize,
+    rd_ops: usize,
+    wr_bytes: usize,
+    wr_ops: usize,
+    delay_ms: u32,
+}
+
+impl<'a> Tally<'a> {
+    fn new(p: &'a mut dyn Custom) -> Self {
+        Self{ p, rd_bytes: 0, rd_ops: 0, wr_bytes: 0, wr_ops: 0, delay_ms: 0 }
+    }
+}
+
+impl Custom for Tally<'_> {
+    fn rd_bytes(&mut self, index: u16, buf: &mut [u8]) {
+        self.rd_bytes += buf.len();
+        self.rd_ops += 1;
+        self.p.rd_bytes(index, buf)
+    }
+    fn wr_bytes(&mut self, index: u16, vs: &[u8]) {
+        self.wr_bytes += vs.len();
+        self.wr_ops += 1;
+        self.p.wr_bytes(index, vs)
+    }
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay_ms += ms;
+        self.p.delay_ms(ms)
+    }
+    fn addr_changed(&mut self, addr: &I2cAddr) {
+        self.p.addr_changed(addr)
+    }
+    fn max_read_len(&self) -> usize {
+        self.p.max_read_len()
+    }
+    fn on_progress(&mut self) {
+        self.p.on_progress()
+    }
+}
+
+/*
+* How to treat the '(device id, rev id)' read at ping.
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, core::fmt::Debug)]
+pub enum PingPolicy<'a> {
+    Strict,                         // only '(0xf0, 0x02)', like the vendor driver
+    AcceptList(&'a [(u8,u8)]),      // also these (with a warning)
+    Skip                            // don't ping at all (with a warning); '.supervise()' then can't tell a reboot
+}
+
 /**
 * Function, modeled akin to the vendor ULD 'vl53l5cx_is_alive()', but:
 *   - made in Rust
@@ -202,14 +476,14 @@ impl<P: Custom + 'static> VL53L5CX<P> {
 * Note:
 *   - Vendor's ULD C driver expects '(0xf0, 0x02)'.
 */
-fn vl53l5cx_ping<P : Custom>(pl: &mut P) -> CoreResult<(u8,u8),()> {
+fn vl53l5cx_ping<P : Custom>(pl: &mut P) -> (u8,u8) {
     let mut buf = [u8::MAX;2];
 
     pl.wr_bytes(0x7fff, &[0x00]);
     pl.rd_bytes(0, &mut buf);   // [dev_id, rev_id]
     pl.wr_bytes(0x7fff, &[0x02]);
 
-    Ok( (buf[0], buf[1]) )
+    (buf[0], buf[1])
 }
 
 /*
@@ -227,11 +501,27 @@ impl I2cAddr {
         Self(v >> 1)
     }
     pub fn from_7bit(v: u8) -> Self {
-        assert!(v < 0x80, "not 7-bit");
+        uld_assert!(v < 0x80, "not 7-bit");
         Self(v)
     }
     pub const fn as_7bit(&self) -> u8 { self.0 }      // used by platform code (needs to be 'pub')
-    //fn as_8bit(&self) -> u8 { self.0 << 1 }
+    pub const fn as_8bit(&self) -> u8 { self.0 << 1 }     // vendor docs use these
+}
+
+/*
+* Always with the explicit suffix, e.g. "0x29_u7". Applies to all logs and errors that print an
+* address; the 0x52-vs-0x29 confusion is real.
+*/
+impl Display for I2cAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#04x}_u7", self.as_7bit())
+    }
+}
+
+impl core::fmt::Debug for I2cAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "I2cAddr({:#04x}_u7 = {:#04x}_u8)", self.as_7bit(), self.as_8bit())
+    }
 }
 
 #[cfg(feature = "_defmt")]
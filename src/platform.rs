@@ -5,7 +5,7 @@
 
 #[cfg(feature = "_defmt")]
 #[allow(unused_imports)]
-use defmt::{trace, warn};
+use defmt::{error, trace, warn};
 
 use core::{
     ffi::c_void,
@@ -14,7 +14,7 @@ use core::{
 
 use crate::I2cAddr;
 use crate::uld_raw::{
-    ST_OK,
+    ST_OK, ST_ERROR,
     VL53L5CX_Platform
 };
 
@@ -33,6 +33,30 @@ pub trait Custom {
     // address the device identifies with, inform the 'Platform' struct about it.
     //
     fn addr_changed(&mut self, addr: &I2cAddr);
+
+    // Largest single read the platform (HAL) can handle, in bytes. Longer reads by the ULD are
+    // split into multiple 'rd_bytes()' calls.
+    //
+    fn max_read_len(&self) -> usize { usize::MAX }
+
+    // Called after each multi-byte write (in pieces of max. 4kB; ~90ms at 400kHz) and each wait
+    // the ULD does. Single byte writes don't call it. Feed a hardware watchdog here, if you have
+    // a tight one.
+    //
+    fn on_progress(&mut self) {}
+}
+
+/*
+* Forwarding through a reference. Mainly for '&'static mut dyn Custom' (see 'DynSensor'), which
+* lets firmware with heterogeneous platforms use just one instance of the (generic) driver code.
+*/
+impl<T: Custom + ?Sized> Custom for &mut T {
+    fn rd_bytes(&mut self, index: u16, buf: &mut [u8]) { (**self).rd_bytes(index, buf) }
+    fn wr_bytes(&mut self, index: u16, vs: &[u8]) { (**self).wr_bytes(index, vs) }
+    fn delay_ms(&mut self, ms: u32) { (**self).delay_ms(ms) }
+    fn addr_changed(&mut self, addr: &I2cAddr) { (**self).addr_changed(addr) }
+    fn max_read_len(&self) -> usize { (**self).max_read_len() }
+    fn on_progress(&mut self) { (**self).on_progress() }
 }
 
 /*
@@ -97,8 +121,22 @@ pub extern "C" fn VL53L5CX_RdMulti(
     p_values: *mut u8,
     size: u32   // size_t
 ) -> u8 {
+    // The index space is 16 bits; a read crossing its end would (at best) wrap around. Don't
+    // let such through, or silently truncate 'size' (it's 'u32').
+    //
+    if (addr as u64) + (size as u64) > 0x1_0000 {
+        error!("Read of {=u32} bytes at {:#06x} goes past the 16-bit index space", size, addr);
+        return ST_ERROR;
+    }
+
     with(pt, |p| {
-        p.rd_bytes(addr, unsafe { slice::from_raw_parts_mut(p_values, size as usize) } );
+        let buf = unsafe { slice::from_raw_parts_mut(p_values, size as usize) };
+        let max = p.max_read_len().max(1);
+
+        // Split to what the platform can take. The sensor's index keeps up with us.
+        for (i, chunk) in buf.chunks_mut(max).enumerate() {
+            p.rd_bytes(addr + (i*max) as u16, chunk);
+        }
         ST_OK
     })
 }
@@ -116,12 +154,27 @@ pub extern "C" fn VL53L5CX_WrMulti(
     p_values: *mut u8,  // *u8 (const)
     size: u32   // actual values fit 16 bits; size_t
 ) -> u8 {
+    if (addr as u64) + (size as u64) > 0x1_0000 {
+        error!("Write of {=u32} bytes at {:#06x} goes past the 16-bit index space", size, addr);
+        return ST_ERROR;
+    }
+
     with(pt, |p| {
-        p.wr_bytes(addr, unsafe { slice::from_raw_parts(p_values, size as usize) } );
+        let vs = unsafe { slice::from_raw_parts(p_values, size as usize) };
+
+        // The firmware upload comes in 32kB writes (~740ms each, at 400kHz). Split them, so that
+        // '.on_progress()' gets called often enough for a tight watchdog.
+        for (i, chunk) in vs.chunks(WR_CHUNK).enumerate() {
+            p.wr_bytes(addr + (i*WR_CHUNK) as u16, chunk);
+            p.on_progress();
+        }
         ST_OK
     })
 }
 
+// ~90ms at 400kHz
+const WR_CHUNK: usize = 4096;
+
 // NOTE: Vendor docs don't really describe what the "4-byte grouping" means, but their 'protocol.c'
 //      comments provide the details.
 //
@@ -134,7 +187,7 @@ pub extern "C" fn VL53L5CX_SwapBuffer(buf: *mut u8, size: u16 /*size in bytes; n
     // Note: Since we don't actually _know_, whether 'buffer' is 4-byte aligned (to be used as '*mut u32'),
     // The original doc mentions a blurry "generally uint32_t" (not very helpful).
     //
-    assert!(buf as usize %4 <= 0, "Buffer to swap byte order not 'u32' aligned");   // '<= 0' to avoid an IDE warning
+    uld_assert!(buf as usize %4 <= 0, "Buffer to swap byte order not 'u32' aligned");   // '<= 0' to avoid an IDE warning
 
     let words: usize = (size as usize)/4;
     let s: &mut[u32] = unsafe { slice::from_raw_parts_mut(buf as *mut u32, words) };
@@ -150,10 +203,11 @@ pub extern "C" fn VL53L5CX_SwapBuffer(buf: *mut u8, size: u16 /*size in bytes; n
 /// @return (uint8_t) status : 0 if wait is finished
 #[unsafe(no_mangle)]
 pub extern "C" fn VL53L5CX_WaitMs(pt: *mut VL53L5CX_Platform, time_ms: u32) -> u8 {
-    assert!(time_ms <= 100, "Unexpected long wait: {}ms", time_ms);    // we know from the C code there's no >100
+    uld_assert!(time_ms <= 100, "Unexpected long wait: {}ms", time_ms);    // we know from the C code there's no >100
 
     with(pt, |p| {
         p.delay_ms(time_ms);
+        p.on_progress();
         ST_OK
     })
 }
@@ -0,0 +1,186 @@
+/*
+* Introspection: which state the sensor is in, and how it's configured.
+*
+* These are read back from the sensor (via the ULD), so the application doesn't need to mirror
+* the configuration separately, e.g. for a debug console.
+*/
+use core::fmt::{Display, Formatter};
+
+use crate::uld_raw::{
+    VL53L5CX_POWER_MODE_SLEEP,
+    VL53L5CX_POWER_MODE_WAKEUP,
+    VL53L5CX_RANGING_MODE_AUTONOMOUS,
+    VL53L5CX_RANGING_MODE_CONTINUOUS,
+    VL53L5CX_RESOLUTION_4X4,
+    VL53L5CX_RESOLUTION_8X8,
+    VL53L5CX_TARGET_ORDER_CLOSEST,
+    VL53L5CX_TARGET_ORDER_STRONGEST,
+};
+
+/*
+* Discriminant of the Rust side state types ('State_HP_Idle' etc.).
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Eq, PartialEq, core::fmt::Debug)]
+#[non_exhaustive]
+pub enum SensorStateKind {
+    HpIdle,
+    LpIdle,
+}
+
+impl Display for SensorStateKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SensorStateKind::HpIdle => write!(f, "HP Idle"),
+            SensorStateKind::LpIdle => write!(f, "LP Idle"),
+        }
+    }
+}
+
+/*
+* Power mode of the sensor. "Sleep" is the vendor's LP Idle state; "wakeup" is HP Idle (or ranging).
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Eq, PartialEq, core::fmt::Debug)]
+pub enum PowerMode {
+    Sleep,
+    Wakeup,
+}
+
+impl PowerMode {
+    // The ULD only gives '0' or '1' (it fails the call on anything else).
+    pub(crate) fn from_uld(v: u8) -> Self {
+        if v as u32 == VL53L5CX_POWER_MODE_SLEEP { PowerMode::Sleep } else { PowerMode::Wakeup }
+    }
+
+    pub(crate) fn to_uld(self) -> u8 {
+        (match self {
+            PowerMode::Sleep => VL53L5CX_POWER_MODE_SLEEP,
+            PowerMode::Wakeup => VL53L5CX_POWER_MODE_WAKEUP,
+        }) as u8
+    }
+}
+
+impl Display for PowerMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PowerMode::Sleep => write!(f, "sleep"),
+            PowerMode::Wakeup => write!(f, "wakeup"),
+        }
+    }
+}
+
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Eq, PartialEq, core::fmt::Debug)]
+pub enum TargetOrder {
+    Closest,
+    Strongest,      // vendor default
+}
+
+impl Display for TargetOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TargetOrder::Closest => write!(f, "closest"),
+            TargetOrder::Strongest => write!(f, "strongest"),
+        }
+    }
+}
+
+impl TargetOrder {
+    pub(crate) fn to_uld(self) -> u8 {
+        (match self {
+            TargetOrder::Closest => VL53L5CX_TARGET_ORDER_CLOSEST,
+            TargetOrder::Strongest => VL53L5CX_TARGET_ORDER_STRONGEST,
+        }) as u8
+    }
+}
+
+/*
+* Named starting points for the configuration, within the limits given in UM2884 (max. 60 Hz
+* for 4x4, 15 Hz for 8x8). All use continuous ranging mode.
+*
+*   - 'MaxRange':   4x4 @ 10 Hz; few, large zones and a long period reach furthest
+*   - 'HighSpeed':  4x4 @ 60 Hz
+*   - 'WideScene':  8x8 @ 15 Hz, sharpener off ("raw"; zones bleed into each other, but nothing
+*                   is masked)
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Eq, PartialEq, core::fmt::Debug)]
+#[non_exhaustive]
+pub enum Profile {
+    MaxRange,
+    HighSpeed,
+    WideScene,
+}
+
+impl Profile {
+    // (resolution, frequency, ranging mode, sharpener, target order) in ULD terms
+    pub(crate) fn to_uld(self) -> (u8, u8, u8, u8, u8) {
+        const CONT: u8 = VL53L5CX_RANGING_MODE_CONTINUOUS as u8;
+        const R4X4: u8 = VL53L5CX_RESOLUTION_4X4 as u8;
+        const R8X8: u8 = VL53L5CX_RESOLUTION_8X8 as u8;
+
+        match self {
+            Profile::MaxRange => (R4X4, 10, CONT, 5, TargetOrder::Strongest.to_uld()),
+            Profile::HighSpeed => (R4X4, 60, CONT, 5, TargetOrder::Strongest.to_uld()),
+            Profile::WideScene => (R8X8, 15, CONT, 0, TargetOrder::Strongest.to_uld()),
+        }
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Profile::MaxRange => write!(f, "max range"),
+            Profile::HighSpeed => write!(f, "high speed"),
+            Profile::WideScene => write!(f, "wide scene"),
+        }
+    }
+}
+
+/*
+* The ranging configuration, as read back from the sensor.
+*
+* Note: Non-exhaustive, so that fields can be added (e.g. with new ULD features) without breaking
+*       applications. Fields are read directly; values are only created by the library.
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, core::fmt::Debug)]
+#[non_exhaustive]
+pub struct SensorConfig {
+    pub zones: u8,              // 16 (4x4) or 64 (8x8)
+    pub freq_hz: u8,
+    pub autonomous: bool,       // ranging mode; 'false' for continuous
+    pub integration_ms: u32,    // only applies to autonomous mode
+    pub sharpener_pct: u8,
+    pub target_order: TargetOrder,
+}
+
+impl SensorConfig {
+    pub(crate) fn from_uld(zones: u8, freq_hz: u8, mode: u8, integration_ms: u32, sharpener_pct: u8, target_order: u8) -> Self {
+        Self {
+            zones,
+            freq_hz,
+            autonomous: mode as u32 == VL53L5CX_RANGING_MODE_AUTONOMOUS,
+            integration_ms,
+            sharpener_pct,
+            target_order: if target_order as u32 == VL53L5CX_TARGET_ORDER_CLOSEST { TargetOrder::Closest } else { TargetOrder::Strongest }
+        }
+    }
+}
+
+/*
+* E.g. "8x8 @ 15 Hz, autonomous (5ms), sharpener 5%, strongest first"
+*/
+impl Display for SensorConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let side = if self.zones == 64 { 8 } else { 4 };
+        write!(f, "{side}x{side} @ {} Hz, ", self.freq_hz)?;
+        if self.autonomous {
+            write!(f, "autonomous ({}ms)", self.integration_ms)?;
+        } else {
+            write!(f, "continuous")?;
+        }
+        write!(f, ", sharpener {}%, {} first", self.sharpener_pct, self.target_order)
+    }
+}
@@ -5,7 +5,7 @@
 *
 *   - HP Idle   // allows transition to the other two
 *   - Ranging
-*   - LP Idle   // we don't (currently) support this mode
+*   - LP Idle   // see 'State_LP_Idle'
 *
 * The larger point is that the Rust API reflects the states. You can have the sensor presented
 * as 'State_HP_Idle', but if you transit to ranging, you no longer have access to that state (unless
@@ -13,44 +13,378 @@
 *
 *   [*]: DS13754 - Rev 12, p.9
 */
+use defmt::{debug, warn};
+
 use crate::{uld_raw::{
+    vl53l5cx_dci_read_data,
+    vl53l5cx_get_integration_time_ms,
     vl53l5cx_get_power_mode,
-    VL53L5CX_Configuration
-}, Error, Result, ST_OK};
+    vl53l5cx_get_ranging_frequency_hz,
+    vl53l5cx_get_ranging_mode,
+    vl53l5cx_get_resolution,
+    vl53l5cx_get_sharpener_percent,
+    vl53l5cx_get_target_order,
+    vl53l5cx_set_power_mode,
+    vl53l5cx_set_ranging_frequency_hz,
+    vl53l5cx_set_ranging_mode,
+    vl53l5cx_set_resolution,
+    vl53l5cx_set_sharpener_percent,
+    vl53l5cx_set_target_order,
+    VL53L5CX_Configuration,
+    VL53L5CX_DCI_PIPE_CONTROL,
+    VL53L5CX_ResultsData,
+    ST_ERROR,
+}, ConfigSlot, Custom, CoreResult, DynSensor, Error, Result, UldOp, PowerMode, Profile, SensorConfig, SensorStateKind, State_LP_Idle, UldStorage, VL53L5CX, ST_OK};
 
 /*
 * The "HP Idle" state (vendor terminology): firmware has been downloaded; ready to range.
 */
 #[allow(non_camel_case_types)]
-pub struct State_HP_Idle {
+pub struct State_HP_Idle<P: Custom + 'static, S: UldStorage = ConfigSlot> {
     // The vendor ULD driver wants to have a "playing ground" (it's called 'Dev', presumably for
     // "device"), in the form of the "configuration" struct. It's not really configuration;
     // more of a driver working memory area where all the state and buffers exist.
     //
     // The good part of this arrangement is, we have separate state when handling multiple sensors. :)
     //
-    // The "state" also carries our 'Platform' struct within it. The ULD code uses it to reach back
-    // to the app level, for MCU hardware access.
+    // The "state" also carries a pointer to our 'Platform' struct within it. The ULD code uses it to
+    // reach back to the app level, for MCU hardware access. The platform itself is owned by us
+    // (see 'p', below).
     //
     // The "state" can be read, but we "MUST not manually change these field[s]". In this Rust API,
     // the whole "state" is kept private, to enforce such read-only nature.
     //
-    uld: VL53L5CX_Configuration,
+    // Where the "state" lives is up to 'S': inline (default), or in an application provided slot.
+    //
+    uld: S,
+
+    // The platform. Owned here (and thus dropped exactly once, with the state); the C side only
+    // ever sees a pointer to it, refreshed before each ULD call (see '.uld()').
+    //
+    p: P,
+
+    label: &'static str,
+
+    id: Option<(u8,u8)>,    // '(device id, rev id)' accepted at '.build()'; 'None' if the ping was skipped
+
+    link_failures: u8,      // consecutive failed '.supervise()' calls
+
+    stats: Stats,
 }
 
-impl State_HP_Idle {
-    pub(crate) fn new(uld: VL53L5CX_Configuration) -> Self {
-        Self{ uld }
+// SAFETY: The raw pointers within 'VL53L5CX_Configuration' are:
+//   - '.default_configuration', '.default_xtalk': point to constant tables within the ULD C
+//     library. They are never written to, and live for the duration of the program.
+//   - '.platform': carries a '*mut dyn Custom' to 'self.p'. It's re-pointed before each ULD call,
+//     so moving the state (to another thread/task) is fine, as long as 'P' itself can be moved;
+//     thus the 'P: Send' bound.
+//
+//   The ULD C code keeps no other (global) state; everything is within 'uld'. That is either
+//   inline, or an exclusive '&'static mut' borrow; both are fine to move.
+//
+//   Note: 'Sync' is not provided (nor needed); all access is via '&mut self'.
+//
+unsafe impl<P: Custom + Send, S: UldStorage> Send for State_HP_Idle<P,S> {}
+
+// Compile time check: the states are 'Send', for any 'Send' platform and either storage.
+const _: () = {
+    fn assert_send<T: Send>() {}
+
+    #[allow(dead_code)]
+    fn check<P: Custom + Send + 'static>() {
+        assert_send::<State_HP_Idle<P>>();
+        assert_send::<State_HP_Idle<P, &'static mut ConfigSlot>>();
+        assert_send::<State_LP_Idle<P>>();
+        assert_send::<State_LP_Idle<P, &'static mut ConfigSlot>>();
+    }
+};
+
+// Compile time check: a type erased 'DynSensor' builds, inits and reaches the state's methods.
+const _: () = {
+    #[allow(dead_code)]
+    fn check(p: &'static mut dyn Custom) -> Option<Stats> {
+        let vl: DynSensor = VL53L5CX::builder(p).build().ok()?;
+        let mut st = vl.init().ok()?;
+        st.power_mode().ok()?;
+        Some(st.stats())
     }
+};
 
-    /* I2C access without consequences
+impl<P: Custom + 'static, S: UldStorage> State_HP_Idle<P,S> {
+    pub(crate) fn new(uld: S, p: P, label: &'static str, id: Option<(u8,u8)>) -> Self {
+        Self{ uld, p, label, id, link_failures: 0, stats: Stats::default() }
+    }
+
+    pub fn label(&self) -> &'static str { self.label }
+
+    /* Access to the ULD "configuration", for passing to the C API. The platform pointer within it
+    * is refreshed, since we may have moved since the last call.
     */
-    pub fn i2c_no_op(&mut self) -> Result<()> {
-        let mut tmp: u8 = 0;
-        match unsafe { vl53l5cx_get_power_mode(&mut self.uld, &mut tmp) } {
+    fn uld(&mut self) -> &mut VL53L5CX_Configuration {
+        let uld = self.uld.slot().uld();
+        uld.attach(&mut self.p);
+        uld
+    }
+
+    /* Give up the sensor, and take back the platform (e.g. to reuse the I2C bus) and the storage
+    * of the ULD working memory (needed for re-init, if it was a '&'static mut ConfigSlot').
+    *
+    * The sensor remains powered and in HP Idle. It can be re-initialized by 'VL53L5CX::builder()';
+    * no power cycle is needed, since 'vl53l5cx_init()' starts by rebooting the sensor (in software).
+    */
+    pub fn release(self) -> (P, S) {
+        (self.p, self.uld)
+    }
+
+    /* Turn a ULD status into a 'Result', counting the failures.
+    */
+    fn status(&mut self, st: u8, op: UldOp) -> Result<()> {
+        match st {
             ST_OK => Ok(()),
-            e => Err(Error(e))
+            e => {
+                self.stats.uld_errors = self.stats.uld_errors.saturating_add(1);
+                if e == ST_ERROR {
+                    self.stats.major_errors = self.stats.major_errors.saturating_add(1);
+                }
+                Err(Error::from_uld(e, op))
+            }
         }
     }
+
+    /* Error counters since creation, or the last '.reset_stats()'.
+    */
+    pub fn stats(&self) -> Stats { self.stats }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /* Read a single value from the ULD C API (one of its 'vl53l5cx_get_...()' functions).
+    */
+    fn get<T: Default>(&mut self, op: UldOp, f: unsafe extern "C" fn(*mut VL53L5CX_Configuration, *mut T) -> u8) -> Result<T> {
+        let mut v: T = T::default();
+        let st = unsafe { f(self.uld(), &mut v) };
+        self.status(st, op).map(|_| v)
+    }
+
+    /* Write a single value via the ULD C API (one of its 'vl53l5cx_set_...()' functions).
+    */
+    pub(crate) fn set<T>(&mut self, op: UldOp, f: unsafe extern "C" fn(*mut VL53L5CX_Configuration, T) -> u8, v: T) -> Result<()> {
+        let st = unsafe { f(self.uld(), v) };
+        self.status(st, op)
+    }
+
+    pub const fn kind(&self) -> SensorStateKind {
+        SensorStateKind::HpIdle
+    }
+
+    /* Read back the ranging configuration from the sensor.
+    */
+    pub fn current_config(&mut self) -> Result<SensorConfig> {
+        let zones: u8 = self.get(UldOp::GetConfig, vl53l5cx_get_resolution)?;
+        let freq_hz: u8 = self.get(UldOp::GetConfig, vl53l5cx_get_ranging_frequency_hz)?;
+        let mode: u8 = self.get(UldOp::GetConfig, vl53l5cx_get_ranging_mode)?;
+        let integration_ms: u32 = self.get(UldOp::GetConfig, vl53l5cx_get_integration_time_ms)?;
+        let sharpener_pct: u8 = self.get(UldOp::GetConfig, vl53l5cx_get_sharpener_percent)?;
+        let target_order: u8 = self.get(UldOp::GetConfig, vl53l5cx_get_target_order)?;
+
+        Ok( SensorConfig::from_uld(zones, freq_hz, mode, integration_ms, sharpener_pct, target_order) )
+    }
+
+    /* Configure the sensor by a named profile (see 'Profile').
+    *
+    * Note: Resolution is set first; the allowed frequency range depends on it.
+    */
+    pub fn apply_profile(&mut self, profile: Profile) -> Result<()> {
+        let (resolution, freq_hz, mode, sharpener_pct, target_order) = profile.to_uld();
+
+        self.set(UldOp::SetConfig, vl53l5cx_set_resolution, resolution)?;
+        self.set(UldOp::SetConfig, vl53l5cx_set_ranging_frequency_hz, freq_hz)?;
+        self.set(UldOp::SetConfig, vl53l5cx_set_ranging_mode, mode)?;
+        self.set(UldOp::SetConfig, vl53l5cx_set_sharpener_percent, sharpener_pct)?;
+        self.set(UldOp::SetConfig, vl53l5cx_set_target_order, target_order)?;
+
+        debug!("{=str}: profile '{}' applied", self.label, profile);
+        Ok(())
+    }
+
+    /* Read back the configuration, and tell how the integration time fits the frame period.
+    *
+    * Returns 'Err(Error::OverBudget(..))' if, in autonomous mode, the integration time doesn't
+    * leave room within the period (the sensor cannot meet the requested frequency).
+    */
+    pub fn effective_frame_budget(&mut self) -> Result<FrameBudget> {
+        let SensorConfig{ zones, freq_hz, autonomous, integration_ms, .. } = self.current_config()?;
+
+        let period_ms: u32 = 1000 / (freq_hz.max(1) as u32);
+
+        // Integration time only applies to autonomous mode; in continuous mode, the sensor
+        // integrates for the whole period.
+        //
+        let (duty_pct, margin_ms) = if autonomous {
+            ( (integration_ms * 100 / period_ms).min(100) as u8, Some(period_ms as i32 - integration_ms as i32) )
+        } else {
+            (100, None)
+        };
+
+        let b = FrameBudget{ zones, freq_hz, period_ms, autonomous, integration_ms, duty_pct, margin_ms };
+        debug!("Frame budget: {}", b);
+
+        if matches!(margin_ms, Some(m) if m <= 0) {
+            warn!("Integration time ({=u32}ms) does not fit the frame period ({=u32}ms)", integration_ms, period_ms);
+            return Err(Error::OverBudget(b));
+        }
+        Ok(b)
+    }
+
+    /* Read back the targets per zone, as the ULD C library programmed it to the sensor in
+    * 'vl53l5cx_init()' (its 'VL53L5CX_NB_TARGET_PER_ZONE'), and check it matches what the Rust
+    * bindings ('uld_raw.rs') were generated for.
+    *
+    * Returns 'Err(Error::Mismatch{..})' if not; a sign of linking the wrong prebuilt library (or
+    * stale bindings).
+    */
+    pub fn nb_targets_per_zone(&mut self) -> Result<u8> {
+        // Init writes '{VL53L5CX_NB_TARGET_PER_ZONE, 0x00, 0x01, 0x00}' to the pipe control.
+        let mut buf = [0_u8;4];
+        let st = unsafe { vl53l5cx_dci_read_data(self.uld(), buf.as_mut_ptr(), VL53L5CX_DCI_PIPE_CONTROL, buf.len() as u16) };
+        self.status(st, UldOp::DciRead)?;
+        let library = buf[0];
+
+        if library != TARGETS {
+            warn!("Targets per zone: ULD library uses {=u8}, Rust bindings made for {=u8}", library, TARGETS);
+            return Err(Error::Mismatch{ bindings: TARGETS, library });
+        }
+        Ok(library)
+    }
+
+    /* Read back the power mode. In HP Idle, this should always be 'PowerMode::Wakeup'.
+    */
+    pub fn power_mode(&mut self) -> Result<PowerMode> {
+        let v: u8 = self.get(UldOp::GetPowerMode, vl53l5cx_get_power_mode)?;
+        Ok( PowerMode::from_uld(v) )
+    }
+
+    /* Put the sensor to low power idle ("sleep"). Wake it up by 'State_LP_Idle::wake_up()'.
+    *
+    * The configuration is retained over the sleep. On failure, you get the state back.
+    */
+    pub fn into_lp_idle(mut self) -> CoreResult<State_LP_Idle<P,S>, (Self, Error)> {
+        match self.set(UldOp::SetPowerMode, vl53l5cx_set_power_mode, PowerMode::Sleep.to_uld()) {
+            Ok(()) => {
+                debug!("{=str}: to LP Idle", self.label);
+                Ok( State_LP_Idle::new(self) )
+            },
+            Err(e) => Err((self, e))
+        }
+    }
+
+    /* I2C access without consequences. Reads the power mode, discarding it; use '.power_mode()'
+    * for the value, or '.supervise()' for link checks.
+    */
+    pub fn i2c_no_op(&mut self) -> Result<()> {
+        self.power_mode().map(|_| ())
+    }
+
+    /* Link supervision, for long idle periods. Call periodically (the interval is up to the app;
+    * e.g. every few seconds), to notice a browned-out or disconnected sensor before the next
+    * ranging attempt fails confusingly.
+    *
+    * Does the cheap '.i2c_no_op()' read; the ULD reports an error if the sensor doesn't answer
+    * the power mode query sensibly.
+    *
+    * Returns 'Err(Error::LinkLost(n))', 'n' being the number of consecutive failures so far. Once
+    * 'REBOOT_AFTER' checks in a row have failed, the sensor is pinged; if it still gives the
+    * '(device id, rev id)' accepted at '.build()', 'Err(Error::SensorRebooted)' is returned
+    * instead. Note that the ping doesn't need the firmware, so this is a likely explanation (e.g.
+    * a brown-out), not a proof.
+    *
+    * Note: For a sensor built with 'PingPolicy::Skip', there is no id to compare against; reboot
+    *       detection is not available, and failures stay 'LinkLost'.
+    *
+    * Recovery (from either) is by '.release()' and re-init via 'VL53L5CX::builder()' (init reboots
+    * the sensor). A sensor that doesn't answer at all may need a power cycle first.
+    */
+    pub fn supervise(&mut self) -> Result<()> {
+        match self.i2c_no_op() {
+            Ok(()) => {
+                if self.link_failures > 0 {
+                    debug!("{=str}: link back, after {=u8} failed checks", self.label, self.link_failures);
+                }
+                self.link_failures = 0;
+                Ok(())
+            },
+            Err(e) => {
+                self.link_failures = self.link_failures.saturating_add(1);
+                self.stats.link_failures = self.stats.link_failures.saturating_add(1);
+                warn!("{=str}: link check failed ({=u8} in a row): {}", self.label, self.link_failures, e);
+
+                // A single failure may be a bad transfer; only suspect a reboot after several.
+                // Ping is done directly over the platform; it doesn't need the firmware.
+                if self.link_failures >= REBOOT_AFTER && self.id.is_some_and(|id| crate::vl53l5cx_ping(&mut self.p) == id) {
+                    warn!("{=str}: sensor answers, but keeps failing the checks; likely rebooted", self.label);
+                    return Err(Error::SensorRebooted);
+                }
+                Err(Error::LinkLost(self.link_failures))
+            }
+        }
+    }
+}
+
+
+// Consecutive failed '.supervise()' checks before a sensor that still answers its ID is taken
+// as rebooted.
+//
+const REBOOT_AFTER: u8 = 3;
+
+// Targets per zone the Rust bindings were generated for; visible in the size of the results
+// arrays in 'uld_raw.rs' (64 zones x targets). Whether the linked C library agrees is checked at
+// runtime (see '.nb_targets_per_zone()').
+//
+pub(crate) const TARGETS: u8 = {
+    let r = unsafe { core::mem::MaybeUninit::<VL53L5CX_ResultsData>::zeroed().assume_init() };
+    (r.target_status.len() / 64) as u8
+};
+
+/*
+* Error counters, for telemetry (trending sensor health without parsing logs).
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, Default, core::fmt::Debug)]
+#[non_exhaustive]
+pub struct Stats {
+    pub uld_errors: u32,        // failed ULD calls, any status
+    pub major_errors: u32,      // ..of which with status 255 ("major error"; usually I2C timeout)
+    pub link_failures: u32,     // failed '.supervise()' checks (total, not consecutive)
+}
+
+/*
+* Timing of a frame, as configured.
+*
+* Note: This is a first order estimate. Sensor internal overheads are not accounted for.
+*/
+#[cfg_attr(feature = "_defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, core::fmt::Debug)]
+#[non_exhaustive]
+pub struct FrameBudget {
+    pub zones: u8,              // 16 (4x4) or 64 (8x8)
+    pub freq_hz: u8,
+    pub period_ms: u32,         // 1000 / 'freq_hz'
+    pub autonomous: bool,       // ranging mode; 'false' for continuous
+    pub integration_ms: u32,    // only applies to autonomous mode
+    pub duty_pct: u8,           // share of the period spent integrating
+    pub margin_ms: Option<i32>, // period - integration time; <= 0 means the frequency cannot be met ('None' in continuous mode)
 }
 
+/*
+* E.g. "64 zones @ 15 Hz (66ms): autonomous, integrating 20ms (30%), margin 46ms"
+*/
+impl core::fmt::Display for FrameBudget {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} zones @ {} Hz ({}ms): ", self.zones, self.freq_hz, self.period_ms)?;
+        match self.margin_ms {
+            Some(margin) => write!(f, "autonomous, integrating {}ms ({}%), margin {}ms", self.integration_ms, self.duty_pct, margin),
+            None => write!(f, "continuous")
+        }
+    }
+}
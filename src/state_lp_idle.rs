@@ -0,0 +1,64 @@
+/*
+* State_LP_Idle
+*
+* The "LP Idle" state (vendor terminology): the sensor is asleep, keeping its firmware and
+* configuration. Meant for battery applications, between ranging sessions.
+*
+* Entered from 'State_HP_Idle::into_lp_idle()'; the only way forward is '.wake_up()' (or
+* '.release()'). The HP Idle state is kept within, but its methods are not reachable while asleep.
+*/
+use defmt::debug;
+
+use crate::{uld_raw::vl53l5cx_set_power_mode,
+    ConfigSlot, CoreResult, Custom, Error, PowerMode, Result, SensorStateKind, State_HP_Idle, Stats, UldOp, UldStorage,
+};
+
+#[allow(non_camel_case_types)]
+pub struct State_LP_Idle<P: Custom + 'static, S: UldStorage = ConfigSlot> {
+    hp: State_HP_Idle<P,S>,
+}
+
+impl<P: Custom + 'static, S: UldStorage> State_LP_Idle<P,S> {
+    pub(crate) fn new(hp: State_HP_Idle<P,S>) -> Self {
+        Self{ hp }
+    }
+
+    pub fn label(&self) -> &'static str { self.hp.label() }
+
+    pub const fn kind(&self) -> SensorStateKind {
+        SensorStateKind::LpIdle
+    }
+
+    /* Read back the power mode; should be 'PowerMode::Sleep'. Also works as a link check.
+    */
+    pub fn power_mode(&mut self) -> Result<PowerMode> {
+        self.hp.power_mode()
+    }
+
+    /* Error counters; shared with the HP Idle state (see 'State_HP_Idle::stats()').
+    */
+    pub fn stats(&self) -> Stats { self.hp.stats() }
+
+    pub fn reset_stats(&mut self) {
+        self.hp.reset_stats()
+    }
+
+    /* Back to HP Idle. On failure, you get the (sleeping) state back.
+    */
+    pub fn wake_up(mut self) -> CoreResult<State_HP_Idle<P,S>, (Self, Error)> {
+        match self.hp.set(UldOp::SetPowerMode, vl53l5cx_set_power_mode, PowerMode::Wakeup.to_uld()) {
+            Ok(()) => {
+                debug!("{=str}: to HP Idle", self.hp.label());
+                Ok(self.hp)
+            },
+            Err(e) => Err((self, e))
+        }
+    }
+
+    /* Give up the sensor, and take back the platform and the storage (see
+    * 'State_HP_Idle::release()'). The sensor is left asleep.
+    */
+    pub fn release(self) -> (P, S) {
+        self.hp.release()
+    }
+}
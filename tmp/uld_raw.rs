@@ -84,7 +84,7 @@ unsafe extern "C" {
 }
 unsafe extern "C" {
     #[doc = " @brief This function is used to set the sensor in Low Power mode, for\n example if the sensor is not used during a long time. The macro\n VL53L5CX_POWER_MODE_SLEEP can be used to enable the low power mode. When user\n want to restart the sensor, he can use macro VL53L5CX_POWER_MODE_WAKEUP.\n Please ensure that the device is not streaming before calling the function.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) power_mode : Selected power mode (VL53L5CX_POWER_MODE_SLEEP\n or VL53L5CX_POWER_MODE_WAKEUP)\n @return (uint8_t) status : 0 if power mode is OK, or 127 if power mode\n requested by user is not valid."]
-    pub fn _vl53l5cx_set_power_mode(p_dev: *mut VL53L5CX_Configuration, power_mode: u8) -> u8;
+    pub fn vl53l5cx_set_power_mode(p_dev: *mut VL53L5CX_Configuration, power_mode: u8) -> u8;
 }
 unsafe extern "C" {
     #[doc = " @brief This function starts a ranging session. When the sensor streams, host\n cannot change settings 'on-the-fly'.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @return (uint8_t) status : 0 if start is OK."]
@@ -116,6 +116,41 @@ unsafe extern "C" {
         frequency_hz: u8,
     ) -> u8;
 }
+unsafe extern "C" {
+    #[doc = " @brief This function gets the current resolution (4x4 or 8x8).\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) *p_resolution : Value of this pointer will be equal to 16\n for 4x4 mode, and 64 for 8x8 mode.\n @return (uint8_t) status : 0 if resolution is OK."]
+    pub fn vl53l5cx_get_resolution(p_dev: *mut VL53L5CX_Configuration, p_resolution: *mut u8) -> u8;
+}
+unsafe extern "C" {
+    #[doc = " @brief This function gets the current ranging frequency in Hz. Ranging\n frequency corresponds to the time between each measurement.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) *p_frequency_hz: Contains the ranging frequency in Hz.\n @return (uint8_t) status : 0 if ranging frequency is OK."]
+    pub fn vl53l5cx_get_ranging_frequency_hz(
+        p_dev: *mut VL53L5CX_Configuration,
+        p_frequency_hz: *mut u8,
+    ) -> u8;
+}
+unsafe extern "C" {
+    #[doc = " @brief This function gets the current integration time in ms.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint32_t) *p_time_ms: Contains integration time in ms.\n @return (uint8_t) status : 0 if integration time is OK."]
+    pub fn vl53l5cx_get_integration_time_ms(
+        p_dev: *mut VL53L5CX_Configuration,
+        p_time_ms: *mut u32,
+    ) -> u8;
+}
+unsafe extern "C" {
+    #[doc = " @brief This function is used to get the ranging mode. Two modes are\n available using ULD : Continuous and autonomous. The default\n mode is Autonomous.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) *p_ranging_mode : current ranging mode\n @return (uint8_t) status : 0 if get ranging mode is OK."]
+    pub fn vl53l5cx_get_ranging_mode(p_dev: *mut VL53L5CX_Configuration, p_ranging_mode: *mut u8)
+        -> u8;
+}
+unsafe extern "C" {
+    #[doc = " @brief This function gets the current sharpener in percent. Sharpener can be\n changed to blur more or less zones depending of the application.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint32_t) *p_sharpener_percent : Contains the sharpener in percent.\n @return (uint8_t) status : 0 if get sharpener is OK."]
+    pub fn vl53l5cx_get_sharpener_percent(
+        p_dev: *mut VL53L5CX_Configuration,
+        p_sharpener_percent: *mut u8,
+    ) -> u8;
+}
+unsafe extern "C" {
+    #[doc = " @brief This function gets the current target order (closest or strongest).\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) *p_target_order: Contains the target order.\n @return (uint8_t) status : 0 if get target order is OK."]
+    pub fn vl53l5cx_get_target_order(p_dev: *mut VL53L5CX_Configuration, p_target_order: *mut u8)
+        -> u8;
+}
 unsafe extern "C" {
     #[doc = " @brief This function sets a new integration time in ms. Integration time must\n be computed to be lower than the ranging period, for a selected resolution.\n Please note that this function has no impact on ranging mode continous.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint32_t) time_ms : Contains the integration time in ms. For all\n resolutions and frequency, the minimum value is 2ms, and the maximum is\n 1000ms.\n @return (uint8_t) status : 0 if set integration time is OK."]
     pub fn vl53l5cx_set_integration_time_ms(
@@ -138,7 +173,25 @@ unsafe extern "C" {
     #[doc = " @brief This function is used to set the ranging mode. Two modes are\n available using ULD : Continuous and autonomous. The default\n mode is Autonomous.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) ranging_mode : Use macros VL53L5CX_RANGING_MODE_CONTINUOUS,\n VL53L5CX_RANGING_MODE_CONTINUOUS.\n @return (uint8_t) status : 0 if set ranging mode is OK."]
     pub fn vl53l5cx_set_ranging_mode(p_dev: *mut VL53L5CX_Configuration, ranging_mode: u8) -> u8;
 }
+unsafe extern "C" {
+    #[doc = " @brief This function can be used to read 'extra data' from DCI. Using a known\n index, the function fills the casted structure passed in argument.\n @param (VL53L5CX_Configuration) *p_dev : VL53L5CX configuration structure.\n @param (uint8_t) *data : This field can be a casted structure, or a simple\n array. Please note that the FW only accept data of 32 bits. So field data can\n only have a size of 32, 64, 96, 128, bits ....\n @param (uint32_t) index : Index of required value.\n @param (uint16_t)*data_size : This field must be the structure or array size\n (using sizeof() function).\n @return (uint8_t) status : 0 if OK"]
+    pub fn vl53l5cx_dci_read_data(
+        p_dev: *mut VL53L5CX_Configuration,
+        data: *mut u8,
+        index: u32,
+        data_size: u16,
+    ) -> u8;
+}
 pub const API_REVISION: &[u8; 15] = b"VL53L5CX_2.0.0\0";
+pub const VL53L5CX_RESOLUTION_4X4: u32 = 16;
+pub const VL53L5CX_RESOLUTION_8X8: u32 = 64;
+pub const VL53L5CX_RANGING_MODE_CONTINUOUS: u32 = 1;
+pub const VL53L5CX_RANGING_MODE_AUTONOMOUS: u32 = 3;
+pub const VL53L5CX_TARGET_ORDER_CLOSEST: u32 = 1;
+pub const VL53L5CX_TARGET_ORDER_STRONGEST: u32 = 2;
+pub const VL53L5CX_DCI_PIPE_CONTROL: u32 = 56192;
+pub const VL53L5CX_POWER_MODE_SLEEP: u32 = 0;
+pub const VL53L5CX_POWER_MODE_WAKEUP: u32 = 1;
 #[doc = " @brief Status of operations.\n\n     Note that official documentation only mentions these cases:\n\n         |||\n         |---|---|\n         |0|No error|\n         |127|invalid value (from the application)|\n         |255|major error (usually timeout in I2C)|\n         |other|\"combination of multiple errors\"|\n\n     This means listing anything else in the API would not really make sense.\n\n     Note: Also the app side code ('RdMulti', 'MsWait' etc.) affects the codes.\n"]
 pub const ST_OK: u8 = 0;
 pub const ST_ERROR: u8 = 255;